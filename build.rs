@@ -0,0 +1,16 @@
+use std::process::Command;
+
+fn main() {
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+
+    if let Some(git_hash) = git_hash {
+        println!("cargo:rustc-env=GIT_HASH={git_hash}");
+    }
+
+    println!("cargo:rerun-if-changed=.git/HEAD");
+}
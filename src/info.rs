@@ -0,0 +1,52 @@
+use serde::Serialize;
+use std::fs;
+use std::process::Command;
+use uuid::Uuid;
+
+/// Stable identity and build metadata, captured once at process startup.
+///
+/// Unlike `Stats`, this never changes for the lifetime of the process, so a
+/// fleet of Pis can tell a restart apart from a clock jump: `instance_id` is
+/// regenerated every boot even if `startup_utc` looks unchanged.
+#[derive(Serialize, Clone)]
+pub struct Info {
+    pub machine_id: String,
+    pub instance_id: String,
+    pub startup_utc: String,
+    pub kernel: String,
+    pub arch: String,
+    pub version: String,
+    pub git_hash: String,
+}
+
+fn read_machine_id() -> String {
+    fs::read_to_string("/etc/machine-id")
+        .or_else(|_| fs::read_to_string("/var/lib/dbus/machine-id"))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn uname_field(flag: &str) -> String {
+    Command::new("uname")
+        .arg(flag)
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Builds the identity record. Call this once from `main` and store the
+/// result in shared state rather than re-reading it on every request.
+pub fn build_info() -> Info {
+    Info {
+        machine_id: read_machine_id(),
+        // A fresh id each boot, so restarts are detectable even if the
+        // system clock is wrong and `startup_utc` can't be trusted alone.
+        instance_id: Uuid::new_v4().to_string(),
+        startup_utc: chrono::Utc::now().to_rfc3339(),
+        kernel: uname_field("-r"),
+        arch: uname_field("-m"),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_hash: option_env!("GIT_HASH").unwrap_or("unknown").to_string(),
+    }
+}
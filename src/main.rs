@@ -1,139 +1,118 @@
-use axum::{Json, Router, routing::get};
-use serde::Serialize;
-use std::sync::Arc;
-use sysinfo::{Components, Disks, Networks, System};
-use tokio::sync::RwLock;
-
-use std::process::Command;
-use std::fs;
-use chrono::DateTime;
-
-#[derive(Serialize, Clone)]
-struct Stats {
-    host: String,
-    os: String,
-    total_memory: u64,
-    used_memory: u64,
-    mempercentage: f32,
-    cpu_usage: f32,
-    temp: f32,
-    received: u64,
-    transmitted: u64,
-    total_disk: u64,
-    used_disk: u64,
-    free_disk: u64,
-    uptime_hours: u64,
-    docker_containers: u32,
-    last_update: String,
-}
-
-fn get_docker_count() -> u32 {
-    let output = Command::new("docker")
-        .args(["ps", "-q"])
-        .output();
-
-    match output {
-        Ok(o) => {
-            let s = String::from_utf8_lossy(&o.stdout);
-            s.lines().count() as u32
-        }
-        Err(_) => 0,
+mod info;
+mod sampler;
+mod stats;
+mod units;
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use axum::{
+    Json, Router,
+    http::{StatusCode, header},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::get,
+};
+use futures::stream::Stream;
+use tokio_stream::{StreamExt, wrappers::WatchStream};
+
+use info::build_info;
+use sampler::{spawn_sampler, AppState};
+use stats::{render_prometheus, Stats};
+
+/// Keep-alive ping cadence for idle `/api/stream` connections, overridable
+/// via `PI_LITE_SSE_KEEPALIVE_SECS`, so reverse proxies don't drop them.
+const DEFAULT_SSE_KEEPALIVE_SECS: u64 = 15;
+
+/// Returns the most recently sampled snapshot, published by the background
+/// sampler. Lock-free: a single `load_full()`, no `sysinfo` refresh.
+async fn get_stats(state: AppState) -> Response {
+    match state.latest.load_full() {
+        Some(stats) => Json(stats.as_ref().clone()).into_response(),
+        None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
     }
 }
 
-fn get_last_update() -> String {
-    // Check /var/lib/apt/periodic/update-success-stamp
-    if let Ok(metadata) = fs::metadata("/var/lib/apt/periodic/update-success-stamp") {
-        if let Ok(time) = metadata.modified() {
-            let datetime: DateTime<chrono::Local> = time.into();
-            return datetime.format("%Y-%m-%d %H:%M").to_string();
-        }
-    }
-    
-    // Fallback: Check /var/lib/apt/lists directory modification time
-    if let Ok(metadata) = fs::metadata("/var/lib/apt/lists") {
-        if let Ok(time) = metadata.modified() {
-            let datetime: DateTime<chrono::Local> = time.into();
-            return datetime.format("%Y-%m-%d %H:%M").to_string();
-        }
-    }
-
-    "Unknown".to_string()
+/// Returns the last N sampled snapshots for charting trends in the frontend.
+async fn get_history(state: AppState) -> Json<Vec<Stats>> {
+    Json(state.history.read().await.iter().cloned().collect())
 }
 
-async fn get_stats(system: Arc<RwLock<System>>) -> Json<Stats> {
-    let mut sys = system.write().await;
-    sys.refresh_all();
-
-    let cpu = sys.global_cpu_usage();
-    let networks = Networks::new_with_refreshed_list();
+/// Returns the identity record captured once at startup.
+async fn get_info(state: AppState) -> Response {
+    Json(state.info.as_ref().clone()).into_response()
+}
 
-    let total_received: u64 = networks
-        .iter()
-        .map(|(_, data)| data.total_received())
-        .sum::<u64>()
-        / 1_048_576;
+/// Streams each new snapshot as it's published by the background sampler,
+/// so `index.html` can subscribe with `EventSource` instead of polling
+/// `/api/stats`.
+async fn get_stream(state: AppState) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let keepalive_secs: u64 = std::env::var("PI_LITE_SSE_KEEPALIVE_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_SSE_KEEPALIVE_SECS);
 
-    let total_transmitted: u64 = networks
-        .iter()
-        .map(|(_, data)| data.total_transmitted())
-        .sum::<u64>()
-        / 1_048_576; // Convert to MB
+    let stream = WatchStream::new(state.stats_tx.subscribe()).filter_map(|stats| {
+        stats.map(|stats| Ok(Event::default().json_data(&stats).expect("Stats serializes")))
+    });
 
-    // get temperature
-    let components = Components::new_with_refreshed_list();
-    let mut selecttemp = 0.0; // Default value if no temperature is available
-    for component in components.iter() {
-        if let Some(temp) = component.temperature() {
-            // Assuming the first component's temperature is representative
-            selecttemp = temp;
-        }
-    }
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(Duration::from_secs(keepalive_secs)))
+}
 
-    let disks = Disks::new_with_refreshed_list();
-    let mut disk_total = 0;
-    let mut disk_free = 0;
-    for disk in &disks {
-        disk_total += disk.total_space();
-        disk_free += disk.available_space();
+/// Serves the aggregate numeric fields of `/api/stats` in Prometheus text
+/// exposition format so the monitor can be scraped by existing TSDB/alerting
+/// stacks. The per-entity breakdowns and the string fields are not exported.
+async fn get_metrics(state: AppState) -> Response {
+    match state.latest.load_full() {
+        Some(stats) => (
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+            render_prometheus(&stats),
+        )
+            .into_response(),
+        None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
     }
-    let disk_used = disk_total - disk_free;
-
-    let uptime = System::uptime();
-    let uptime_hours = uptime / 3600;
-
-    Json(Stats {
-        host: System::host_name().unwrap_or_else(|| "Unknown".to_string()),
-        os: format!(
-            "{} {}",
-            System::name().unwrap_or_else(|| "Unknown".to_string()),
-            System::os_version().unwrap_or_else(|| "Unknown".to_string())
-        ),
-        total_memory: sys.total_memory() / 1000024, // Convert to MB
-        used_memory: sys.used_memory() / 1000024,   // Convert to MB
-        mempercentage: sys.used_memory() as f32 / sys.total_memory() as f32 * 100.0,
-        cpu_usage: cpu,
-        temp: selecttemp, // Use 0.0 if temperature is not available
-        received: total_received,
-        transmitted: total_transmitted,
-        total_disk: disk_total / 1000024, // Convert to MB,
-        used_disk: disk_used / 1000024,   // Convert to MB
-        free_disk: disk_free / 1000024,   // Convert to MB
-        uptime_hours,
-        docker_containers: get_docker_count(),
-        last_update: get_last_update(),
-    })
 }
 
 #[tokio::main]
 async fn main() {
-    let shared_system = Arc::new(RwLock::new(System::new_all()));
+    let state = spawn_sampler(build_info());
+
     let app = Router::new()
         .route(
             "/api/stats",
             get({
-                let shared_system = shared_system.clone();
-                move || get_stats(shared_system)
+                let state = state.clone();
+                move || get_stats(state)
+            }),
+        )
+        .route(
+            "/api/history",
+            get({
+                let state = state.clone();
+                move || get_history(state)
+            }),
+        )
+        .route(
+            "/api/info",
+            get({
+                let state = state.clone();
+                move || get_info(state)
+            }),
+        )
+        .route(
+            "/api/stream",
+            get({
+                let state = state.clone();
+                move || get_stream(state)
+            }),
+        )
+        .route(
+            "/metrics",
+            get({
+                let state = state.clone();
+                move || get_metrics(state)
             }),
         )
         .route(
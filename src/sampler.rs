@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use arc_swap::ArcSwapOption;
+use sysinfo::System;
+use tokio::sync::{RwLock, watch};
+
+use crate::info::Info;
+use crate::stats::{build_stats, Stats};
+use crate::units::UnitBase;
+
+/// Default sampling cadence, overridable via `PI_LITE_SAMPLE_INTERVAL_MS`.
+const DEFAULT_SAMPLE_INTERVAL_MS: u64 = 1000;
+
+/// Number of samples retained for `/api/history`, overridable via
+/// `PI_LITE_HISTORY_CAPACITY`.
+const DEFAULT_HISTORY_CAPACITY: usize = 300;
+
+/// Shared state published by the background sampler and read by HTTP handlers.
+///
+/// `latest` uses `ArcSwapOption` rather than a `RwLock` so reads never block:
+/// handlers do a single, lock-free `load_full()` and the sampler never
+/// contends with a reader holding a guard across `.await` points.
+#[derive(Clone)]
+pub struct AppState {
+    pub latest: Arc<ArcSwapOption<Stats>>,
+    pub history: Arc<RwLock<VecDeque<Stats>>>,
+    pub info: Arc<Info>,
+    /// Publishes every new snapshot for `/api/stream` subscribers to push
+    /// live to connected dashboards without polling `/api/stats`.
+    pub stats_tx: watch::Sender<Option<Stats>>,
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Spawns the background task that owns the `System` handle, refreshes it on
+/// a fixed cadence, and publishes each snapshot into `AppState`.
+///
+/// Keeping `System` alive across refreshes (rather than recreating it per
+/// request) is what makes `global_cpu_usage()` meaningful: sysinfo measures
+/// CPU usage as a delta since the previous refresh.
+pub fn spawn_sampler(info: Info) -> AppState {
+    let interval_ms = env_or("PI_LITE_SAMPLE_INTERVAL_MS", DEFAULT_SAMPLE_INTERVAL_MS);
+    let history_capacity = env_or("PI_LITE_HISTORY_CAPACITY", DEFAULT_HISTORY_CAPACITY);
+
+    let unit_base = UnitBase::from_env();
+    let (stats_tx, _) = watch::channel(None);
+
+    let state = AppState {
+        latest: Arc::new(ArcSwapOption::empty()),
+        history: Arc::new(RwLock::new(VecDeque::with_capacity(history_capacity))),
+        info: Arc::new(info),
+        stats_tx,
+    };
+
+    let sampler_state = state.clone();
+    tokio::spawn(async move {
+        let mut sys = System::new_all();
+        let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms));
+        loop {
+            ticker.tick().await;
+            let snapshot = build_stats(&mut sys, unit_base);
+
+            sampler_state.latest.store(Some(Arc::new(snapshot.clone())));
+            // Ignore the send error: it just means no `/api/stream` clients
+            // are currently subscribed.
+            let _ = sampler_state.stats_tx.send(Some(snapshot.clone()));
+
+            let mut history = sampler_state.history.write().await;
+            if history.len() == history_capacity {
+                history.pop_front();
+            }
+            history.push_back(snapshot);
+        }
+    });
+
+    state
+}
@@ -0,0 +1,257 @@
+use chrono::DateTime;
+use serde::Serialize;
+use std::fs;
+use std::process::Command;
+use sysinfo::{Components, Disks, Networks, System};
+
+use crate::units::{format_bytes, UnitBase};
+
+#[derive(Serialize, Clone)]
+pub struct CpuCore {
+    pub name: String,
+    pub usage: f32,
+    pub frequency: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DiskInfo {
+    pub mount_point: String,
+    pub fs_type: String,
+    pub total: u64,
+    pub available: u64,
+    pub used: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct NetInterface {
+    pub name: String,
+    pub received: u64,
+    pub transmitted: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SensorTemp {
+    pub label: String,
+    pub temp: f32,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Stats {
+    pub host: String,
+    pub os: String,
+    pub mempercentage: f32,
+    pub cpu_usage: f32,
+    pub temp: f32,
+    pub uptime_hours: u64,
+    pub docker_containers: u32,
+    pub last_update: String,
+    // Raw byte counts, e.g. for Prometheus scraping or client-side rate
+    // computation. `*_human` companions below are the display layer.
+    pub total_memory_bytes: u64,
+    pub used_memory_bytes: u64,
+    pub received_bytes: u64,
+    pub transmitted_bytes: u64,
+    pub total_disk_bytes: u64,
+    pub used_disk_bytes: u64,
+    pub free_disk_bytes: u64,
+    // Human-readable companions (e.g. "3.2 GiB"), formatted in the unit
+    // base configured via `PI_LITE_UNIT_BASE`.
+    pub total_memory_human: String,
+    pub used_memory_human: String,
+    pub received_human: String,
+    pub transmitted_human: String,
+    pub total_disk_human: String,
+    pub used_disk_human: String,
+    pub free_disk_human: String,
+    /// Unix timestamp (seconds) at which this snapshot was sampled.
+    pub sampled_at: u64,
+    /// Per-entity breakdowns, for dashboards that want more than the
+    /// aggregates above (which core is pegged, which disk is filling up).
+    pub cpu_cores: Vec<CpuCore>,
+    pub disks: Vec<DiskInfo>,
+    pub net_interfaces: Vec<NetInterface>,
+    pub sensor_temps: Vec<SensorTemp>,
+}
+
+/// Renders `stats` as Prometheus text exposition format (version 0.0.4).
+///
+/// `host` and `os` are emitted as constant labels rather than metric values,
+/// and byte-denominated gauges use the raw (unconverted) counters so rates
+/// computed downstream by the scraper are correct.
+pub fn render_prometheus(stats: &Stats) -> String {
+    let labels = format!(
+        "host=\"{}\",os=\"{}\"",
+        stats.host.replace('"', "\\\""),
+        stats.os.replace('"', "\\\"")
+    );
+
+    let mut body = String::new();
+    let mut gauge = |name: &str, value: String| {
+        body.push_str(&format!("# TYPE pi_lite_{name} gauge\n"));
+        body.push_str(&format!("pi_lite_{name}{{{labels}}} {value}\n"));
+    };
+
+    gauge("cpu_usage", stats.cpu_usage.to_string());
+    gauge("total_memory_bytes", stats.total_memory_bytes.to_string());
+    gauge("used_memory_bytes", stats.used_memory_bytes.to_string());
+    gauge("mem_usage_percent", stats.mempercentage.to_string());
+    gauge("temp_celsius", stats.temp.to_string());
+    gauge("network_received_bytes", stats.received_bytes.to_string());
+    gauge(
+        "network_transmitted_bytes",
+        stats.transmitted_bytes.to_string(),
+    );
+    gauge("total_disk_bytes", stats.total_disk_bytes.to_string());
+    gauge("used_disk_bytes", stats.used_disk_bytes.to_string());
+    gauge("free_disk_bytes", stats.free_disk_bytes.to_string());
+    gauge("uptime_hours", stats.uptime_hours.to_string());
+    gauge("docker_containers", stats.docker_containers.to_string());
+
+    body
+}
+
+fn get_docker_count() -> u32 {
+    let output = Command::new("docker").args(["ps", "-q"]).output();
+
+    match output {
+        Ok(o) => {
+            let s = String::from_utf8_lossy(&o.stdout);
+            s.lines().count() as u32
+        }
+        Err(_) => 0,
+    }
+}
+
+fn get_last_update() -> String {
+    // Check /var/lib/apt/periodic/update-success-stamp
+    if let Ok(metadata) = fs::metadata("/var/lib/apt/periodic/update-success-stamp") {
+        if let Ok(time) = metadata.modified() {
+            let datetime: DateTime<chrono::Local> = time.into();
+            return datetime.format("%Y-%m-%d %H:%M").to_string();
+        }
+    }
+
+    // Fallback: Check /var/lib/apt/lists directory modification time
+    if let Ok(metadata) = fs::metadata("/var/lib/apt/lists") {
+        if let Ok(time) = metadata.modified() {
+            let datetime: DateTime<chrono::Local> = time.into();
+            return datetime.format("%Y-%m-%d %H:%M").to_string();
+        }
+    }
+
+    "Unknown".to_string()
+}
+
+/// Refreshes `sys` and builds a `Stats` snapshot from it.
+///
+/// `sys` is expected to be kept alive by the caller and passed in on a
+/// regular cadence (rather than recreated each call) so that `global_cpu_usage()` reflects
+/// the elapsed inter-sample delta instead of being meaningless on a cold read.
+/// `unit_base` controls whether `*_human` fields use binary (KiB/MiB/GiB) or
+/// decimal (kB/MB/GB) magnitude prefixes.
+pub fn build_stats(sys: &mut System, unit_base: UnitBase) -> Stats {
+    sys.refresh_all();
+
+    let cpu = sys.global_cpu_usage();
+    let cpu_cores: Vec<CpuCore> = sys
+        .cpus()
+        .iter()
+        .map(|cpu| CpuCore {
+            name: cpu.name().to_string(),
+            usage: cpu.cpu_usage(),
+            frequency: cpu.frequency(),
+        })
+        .collect();
+
+    let networks = Networks::new_with_refreshed_list();
+
+    let total_received_bytes: u64 = networks.iter().map(|(_, data)| data.total_received()).sum();
+    let total_transmitted_bytes: u64 = networks
+        .iter()
+        .map(|(_, data)| data.total_transmitted())
+        .sum();
+
+    let net_interfaces: Vec<NetInterface> = networks
+        .iter()
+        .map(|(name, data)| NetInterface {
+            name: name.clone(),
+            received: data.total_received(),
+            transmitted: data.total_transmitted(),
+        })
+        .collect();
+
+    // get temperature
+    let components = Components::new_with_refreshed_list();
+    let mut selecttemp = 0.0; // Default value if no temperature is available
+    for component in components.iter() {
+        if let Some(temp) = component.temperature() {
+            // The last component that reports a temperature wins
+            selecttemp = temp;
+        }
+    }
+    let sensor_temps: Vec<SensorTemp> = components
+        .iter()
+        .filter_map(|component| {
+            component.temperature().map(|temp| SensorTemp {
+                label: component.label().to_string(),
+                temp,
+            })
+        })
+        .collect();
+
+    let disks = Disks::new_with_refreshed_list();
+    let mut disk_total = 0;
+    let mut disk_free = 0;
+    for disk in &disks {
+        disk_total += disk.total_space();
+        disk_free += disk.available_space();
+    }
+    let disk_used = disk_total - disk_free;
+    let disk_infos: Vec<DiskInfo> = disks
+        .iter()
+        .map(|disk| DiskInfo {
+            mount_point: disk.mount_point().to_string_lossy().to_string(),
+            fs_type: disk.file_system().to_string_lossy().to_string(),
+            total: disk.total_space(),
+            available: disk.available_space(),
+            used: disk.total_space() - disk.available_space(),
+        })
+        .collect();
+
+    let uptime = System::uptime();
+    let uptime_hours = uptime / 3600;
+
+    Stats {
+        host: System::host_name().unwrap_or_else(|| "Unknown".to_string()),
+        os: format!(
+            "{} {}",
+            System::name().unwrap_or_else(|| "Unknown".to_string()),
+            System::os_version().unwrap_or_else(|| "Unknown".to_string())
+        ),
+        mempercentage: sys.used_memory() as f32 / sys.total_memory() as f32 * 100.0,
+        cpu_usage: cpu,
+        temp: selecttemp, // Use 0.0 if temperature is not available
+        uptime_hours,
+        docker_containers: get_docker_count(),
+        last_update: get_last_update(),
+        total_memory_bytes: sys.total_memory(),
+        used_memory_bytes: sys.used_memory(),
+        received_bytes: total_received_bytes,
+        transmitted_bytes: total_transmitted_bytes,
+        total_disk_bytes: disk_total,
+        used_disk_bytes: disk_used,
+        free_disk_bytes: disk_free,
+        total_memory_human: format_bytes(sys.total_memory(), unit_base),
+        used_memory_human: format_bytes(sys.used_memory(), unit_base),
+        received_human: format_bytes(total_received_bytes, unit_base),
+        transmitted_human: format_bytes(total_transmitted_bytes, unit_base),
+        total_disk_human: format_bytes(disk_total, unit_base),
+        used_disk_human: format_bytes(disk_used, unit_base),
+        free_disk_human: format_bytes(disk_free, unit_base),
+        sampled_at: chrono::Utc::now().timestamp() as u64,
+        cpu_cores,
+        disks: disk_infos,
+        net_interfaces,
+        sensor_temps,
+    }
+}
@@ -0,0 +1,48 @@
+/// Which magnitude prefixes `format_bytes` should use.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum UnitBase {
+    /// Powers of 1024 (KiB, MiB, GiB, ...) — what most OS tools report.
+    Binary,
+    /// Powers of 1000 (kB, MB, GB, ...) — SI/decimal convention.
+    Decimal,
+}
+
+impl UnitBase {
+    /// Reads the unit base from `PI_LITE_UNIT_BASE` (`"binary"` or
+    /// `"decimal"`), defaulting to binary since that's what the values this
+    /// crate reports (memory, disk) are measured in natively.
+    pub fn from_env() -> Self {
+        match std::env::var("PI_LITE_UNIT_BASE").as_deref() {
+            Ok("decimal") => UnitBase::Decimal,
+            _ => UnitBase::Binary,
+        }
+    }
+}
+
+const BINARY_UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+const DECIMAL_UNITS: [&str; 5] = ["B", "kB", "MB", "GB", "TB"];
+
+/// Formats a raw byte count as a human-readable string (e.g. `"3.2 GiB"`),
+/// picking the largest unit in `base` for which the value is still >= 1.
+pub fn format_bytes(bytes: u64, base: UnitBase) -> String {
+    let (step, units) = match base {
+        UnitBase::Binary => (1024.0, BINARY_UNITS),
+        UnitBase::Decimal => (1000.0, DECIMAL_UNITS),
+    };
+
+    let mut value = bytes as f64;
+    let mut unit = units[0];
+    for &candidate in &units[1..] {
+        if value < step {
+            break;
+        }
+        value /= step;
+        unit = candidate;
+    }
+
+    if unit == units[0] {
+        format!("{value:.0} {unit}")
+    } else {
+        format!("{value:.1} {unit}")
+    }
+}